@@ -0,0 +1,442 @@
+//! Audio carrier support for LSB steganography.
+//!
+//! All supported containers are bit-exact lossless codecs, so hiding data in the
+//! low bit of each decoded PCM sample survives a decode/re-encode round trip.
+//! Lossy containers (MP3, etc.) are deliberately not supported here: re-encoding
+//! would scramble or discard the hidden bits.
+
+use crate::SteganoError;
+use crate::matrix;
+use crate::position::{self, PositionScheme};
+use crate::redundancy;
+use hound::{WavReader, WavWriter};
+use rand::RngCore;
+use std::io::Cursor;
+
+/// Which lossless container a carrier is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Wav,
+    Flac,
+}
+
+/// PCM format parameters, independent of the container they were read from.
+#[derive(Debug, Clone, Copy)]
+pub struct AudioSpec {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+}
+
+/// A carrier decoded down to raw integer samples, ready for LSB manipulation.
+pub struct DecodedAudio {
+    pub codec: AudioCodec,
+    pub spec: AudioSpec,
+    pub samples: Vec<i32>,
+}
+
+/// Sniff the container from its leading bytes.
+fn sniff_codec(data: &[u8]) -> Result<AudioCodec, SteganoError> {
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+        return Ok(AudioCodec::Wav);
+    }
+    if data.starts_with(b"fLaC") {
+        return Ok(AudioCodec::Flac);
+    }
+    if data.len() >= 4 && &data[0..4] == b"wvpk" {
+        // WavPack is lossless and would be a natural fit here, but there is no
+        // maintained pure-Rust WavPack encoder yet, so we can decode-sniff it but
+        // cannot round-trip it. TTA and Monkey's Audio are in the same boat.
+        return Err(SteganoError::UnsupportedAudioFormat);
+    }
+    Err(SteganoError::UnsupportedAudioFormat)
+}
+
+fn decode_wav(data: &[u8]) -> Result<DecodedAudio, SteganoError> {
+    let cursor = Cursor::new(data);
+    let reader = WavReader::new(cursor).map_err(|e| SteganoError::AudioError(e.to_string()))?;
+    let spec = reader.spec();
+    let samples: Vec<i32> = reader
+        .into_samples::<i32>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SteganoError::AudioError(e.to_string()))?;
+
+    Ok(DecodedAudio {
+        codec: AudioCodec::Wav,
+        spec: AudioSpec {
+            channels: spec.channels,
+            sample_rate: spec.sample_rate,
+            bits_per_sample: spec.bits_per_sample,
+        },
+        samples,
+    })
+}
+
+fn decode_flac(data: &[u8]) -> Result<DecodedAudio, SteganoError> {
+    let cursor = Cursor::new(data);
+    let mut reader =
+        claxon::FlacReader::new(cursor).map_err(|e| SteganoError::AudioError(e.to_string()))?;
+    let info = reader.streaminfo();
+
+    let mut samples = Vec::with_capacity(info.samples.unwrap_or(0) as usize * info.channels as usize);
+    for sample in reader.samples() {
+        samples.push(sample.map_err(|e| SteganoError::AudioError(e.to_string()))?);
+    }
+
+    Ok(DecodedAudio {
+        codec: AudioCodec::Flac,
+        spec: AudioSpec {
+            channels: info.channels as u16,
+            sample_rate: info.sample_rate,
+            bits_per_sample: info.bits_per_sample as u16,
+        },
+        samples,
+    })
+}
+
+/// Decode any supported lossless container down to raw samples.
+pub fn decode_audio(audio_data: &[u8]) -> Result<DecodedAudio, SteganoError> {
+    match sniff_codec(audio_data)? {
+        AudioCodec::Wav => decode_wav(audio_data),
+        AudioCodec::Flac => decode_flac(audio_data),
+    }
+}
+
+fn encode_wav(spec: AudioSpec, samples: &[i32]) -> Result<Vec<u8>, SteganoError> {
+    let hound_spec = hound::WavSpec {
+        channels: spec.channels,
+        sample_rate: spec.sample_rate,
+        bits_per_sample: spec.bits_per_sample,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut output_buffer = Cursor::new(Vec::new());
+    {
+        let mut writer = WavWriter::new(&mut output_buffer, hound_spec)
+            .map_err(|e| SteganoError::AudioError(e.to_string()))?;
+
+        for &sample in samples {
+            match spec.bits_per_sample {
+                8 => writer
+                    .write_sample(sample as i8)
+                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
+                16 => writer
+                    .write_sample(sample as i16)
+                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
+                24 | 32 => writer
+                    .write_sample(sample)
+                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
+                _ => return Err(SteganoError::UnsupportedAudioFormat),
+            }
+        }
+
+        writer
+            .finalize()
+            .map_err(|e| SteganoError::AudioError(e.to_string()))?;
+    }
+
+    Ok(output_buffer.into_inner())
+}
+
+fn encode_flac(spec: AudioSpec, samples: &[i32]) -> Result<Vec<u8>, SteganoError> {
+    let config = flacenc::config::Encoder::default();
+    let source = flacenc::source::MemSource::from_samples(
+        samples,
+        spec.channels as usize,
+        spec.bits_per_sample as usize,
+        spec.sample_rate as usize,
+    );
+    let flac_stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| SteganoError::AudioError(format!("{e:?}")))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    flac_stream
+        .write(&mut sink)
+        .map_err(|e| SteganoError::AudioError(format!("{e:?}")))?;
+
+    Ok(sink.as_slice().to_vec())
+}
+
+/// Re-encode samples losslessly into the same container they were decoded from.
+pub fn encode_audio(codec: AudioCodec, spec: AudioSpec, samples: &[i32]) -> Result<Vec<u8>, SteganoError> {
+    match codec {
+        AudioCodec::Wav => encode_wav(spec, samples),
+        AudioCodec::Flac => encode_flac(spec, samples),
+    }
+}
+
+/// A raw (ungrouped) byte worth of carrier slots.
+const RAW_BYTE_BITS: usize = 8;
+
+fn write_raw_byte(samples: &mut [i32], start_slot: usize, byte: u8) {
+    for i in 0..RAW_BYTE_BITS {
+        let bit = (byte >> (RAW_BYTE_BITS - 1 - i)) & 1;
+        samples[start_slot + i] = (samples[start_slot + i] & !1) | bit as i32;
+    }
+}
+
+fn read_raw_byte(samples: &[i32], start_slot: usize) -> u8 {
+    (0..RAW_BYTE_BITS).fold(0u8, |byte, i| (byte << 1) | (samples[start_slot + i] & 1) as u8)
+}
+
+/// Fixed header size (scheme flag + seed salt + redundancy factor) ahead of
+/// the pseudorandomly ordered, redundantly embedded payload.
+fn fixed_header_bits(scheme: PositionScheme) -> usize {
+    let salt_bits = match scheme {
+        PositionScheme::Sequential => 0,
+        PositionScheme::Random => position::SEED_SALT_SIZE * RAW_BYTE_BITS,
+    };
+    RAW_BYTE_BITS + salt_bits + RAW_BYTE_BITS
+}
+
+/// Embed data into audio samples using matrix-encoded LSB steganography at
+/// `scheme`-ordered positions (pseudorandom positions need a passphrase-derived
+/// seed; sequential positions don't), hiding `k` message bits per group of
+/// `2^k - 1` sample LSBs (`k = 1` is plain LSB replacement), with each
+/// resulting LSB written redundantly to `r` spread-out samples (`r = 1` is a
+/// single copy).
+pub fn embed_data_audio(
+    audio_data: &[u8],
+    data: &[u8],
+    passphrase: &str,
+    k: u8,
+    r: u8,
+    scheme: PositionScheme,
+) -> Result<Vec<u8>, SteganoError> {
+    if k == 0 || k > matrix::MAX_K || r == 0 {
+        return Err(SteganoError::InvalidFormat);
+    }
+
+    let decoded = decode_audio(audio_data)?;
+    let total_slots = decoded.samples.len();
+
+    let mut seed_salt = [0u8; position::SEED_SALT_SIZE];
+    let seed_key = match scheme {
+        PositionScheme::Sequential => [0u8; 32],
+        PositionScheme::Random => {
+            rand::thread_rng().fill_bytes(&mut seed_salt);
+            crate::derive_key(passphrase, &seed_salt)?
+        }
+    };
+
+    let fixed_bits = fixed_header_bits(scheme);
+    if fixed_bits > total_slots {
+        return Err(SteganoError::MessageTooLarge);
+    }
+    let remaining_slots = total_slots - fixed_bits;
+
+    let logical_needed = matrix::payload_slots_needed(data.len(), k);
+    let physical_needed = redundancy::slots_needed(logical_needed, r);
+    if physical_needed > remaining_slots {
+        return Err(SteganoError::MessageTooLarge);
+    }
+
+    let order = position::build_order(scheme, seed_key, remaining_slots);
+
+    let mut modified_samples = decoded.samples.clone();
+    write_raw_byte(&mut modified_samples, 0, scheme.as_flag());
+    if scheme == PositionScheme::Random {
+        for (i, &byte) in seed_salt.iter().enumerate() {
+            write_raw_byte(&mut modified_samples, RAW_BYTE_BITS + i * RAW_BYTE_BITS, byte);
+        }
+    }
+    write_raw_byte(&mut modified_samples, fixed_bits - RAW_BYTE_BITS, r);
+
+    let mut lsbs: Vec<u8> = (0..logical_needed)
+        .map(|i| (modified_samples[fixed_bits + order[i * r as usize]] & 1) as u8)
+        .collect();
+    matrix::embed_payload(&mut lsbs, data, k);
+    redundancy::scatter(&order, 0, &lsbs, r, |slot, bit| {
+        let sample = &mut modified_samples[fixed_bits + slot];
+        *sample = (*sample & !1) | bit as i32;
+    });
+
+    encode_audio(decoded.codec, decoded.spec, &modified_samples)
+}
+
+/// A `k` read back from untrusted carrier bits, bounds-checked against the
+/// shift width `matrix::group_size` uses so a garbage header can't panic it.
+fn valid_k(k: u8, logical_remaining: usize) -> bool {
+    k != 0 && (k as u32) < usize::BITS && matrix::group_size(k) <= logical_remaining
+}
+
+/// Extract data from audio samples using matrix-encoded LSB steganography,
+/// re-deriving the same pseudorandom position order the embedder used and
+/// majority-voting each redundant group of samples back to a single bit.
+/// Returns the recovered data along with a report of how much of the
+/// carrier's redundancy was needed to recover it cleanly.
+pub fn extract_data_audio(
+    audio_data: &[u8],
+    passphrase: &str,
+) -> Result<(Vec<u8>, redundancy::IntegrityReport), SteganoError> {
+    match extract_data_audio_current(audio_data, passphrase) {
+        Ok(result) => Ok(result),
+        Err(_) => extract_data_audio_legacy(audio_data),
+    }
+}
+
+fn extract_data_audio_current(
+    audio_data: &[u8],
+    passphrase: &str,
+) -> Result<(Vec<u8>, redundancy::IntegrityReport), SteganoError> {
+    let decoded = decode_audio(audio_data)?;
+    let samples = decoded.samples;
+    let total_slots = samples.len();
+
+    if total_slots < RAW_BYTE_BITS {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let scheme =
+        PositionScheme::from_flag(read_raw_byte(&samples, 0)).ok_or(SteganoError::NoMessageFound)?;
+
+    let fixed_bits = fixed_header_bits(scheme);
+    if fixed_bits > total_slots {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let seed_key = match scheme {
+        PositionScheme::Sequential => [0u8; 32],
+        PositionScheme::Random => {
+            let mut seed_salt = [0u8; position::SEED_SALT_SIZE];
+            for (i, byte) in seed_salt.iter_mut().enumerate() {
+                *byte = read_raw_byte(&samples, RAW_BYTE_BITS + i * RAW_BYTE_BITS);
+            }
+            crate::derive_key(passphrase, &seed_salt)?
+        }
+    };
+    let r = read_raw_byte(&samples, fixed_bits - RAW_BYTE_BITS);
+    if r == 0 {
+        return Err(SteganoError::NoMessageFound);
+    }
+
+    let remaining_slots = total_slots - fixed_bits;
+    let order = position::build_order(scheme, seed_key, remaining_slots);
+    let get_slot = |slot: usize| (samples[fixed_bits + slot] & 1) as u8;
+
+    let (header_lsbs, _) = redundancy::gather(&order, 0, matrix::K_HEADER_BITS, r, get_slot)?;
+    let mut k = 0u8;
+    for &bit in &header_lsbs {
+        k = (k << 1) | bit;
+    }
+    let logical_remaining = remaining_slots / r as usize;
+    if !valid_k(k, logical_remaining) {
+        return Err(SteganoError::NoMessageFound);
+    }
+
+    let length_slots = matrix::slots_needed(32, k);
+    if matrix::K_HEADER_BITS + length_slots > logical_remaining {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let (header_and_length_lsbs, _) =
+        redundancy::gather(&order, 0, matrix::K_HEADER_BITS + length_slots, r, get_slot)?;
+    let (_, data_length) = matrix::read_header(&header_and_length_lsbs);
+
+    let max_bytes = matrix::max_payload_bytes(logical_remaining, k);
+    if data_length > max_bytes || data_length == 0 {
+        return Err(SteganoError::NoMessageFound);
+    }
+
+    let logical_needed = matrix::payload_slots_needed(data_length, k);
+    if logical_needed > logical_remaining {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let (lsbs, report) = redundancy::gather(&order, 0, logical_needed, r, get_slot)?;
+
+    Ok((matrix::extract_payload_data(&lsbs, k, data_length), report))
+}
+
+/// Fall back to the pre-chunk0-4 wire format: before embedding gained a
+/// scheme/salt/redundancy header, the matrix-encoded payload started
+/// immediately at sample 0 with no redundancy. Carriers written by that
+/// version can't flag themselves as such, so this is only tried once the
+/// current header-based format fails to parse.
+fn extract_data_audio_legacy(
+    audio_data: &[u8],
+) -> Result<(Vec<u8>, redundancy::IntegrityReport), SteganoError> {
+    let decoded = decode_audio(audio_data)?;
+    let samples = decoded.samples;
+    let total_slots = samples.len();
+
+    if total_slots < matrix::K_HEADER_BITS {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let get_slot = |slot: usize| (samples[slot] & 1) as u8;
+
+    let mut k = 0u8;
+    for i in 0..matrix::K_HEADER_BITS {
+        k = (k << 1) | get_slot(i);
+    }
+    if !valid_k(k, total_slots) {
+        return Err(SteganoError::NoMessageFound);
+    }
+
+    let length_slots = matrix::slots_needed(32, k);
+    if matrix::K_HEADER_BITS + length_slots > total_slots {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let header_and_length_lsbs: Vec<u8> =
+        (0..matrix::K_HEADER_BITS + length_slots).map(get_slot).collect();
+    let (_, data_length) = matrix::read_header(&header_and_length_lsbs);
+
+    let max_bytes = matrix::max_payload_bytes(total_slots, k);
+    if data_length > max_bytes || data_length == 0 {
+        return Err(SteganoError::NoMessageFound);
+    }
+
+    let needed = matrix::payload_slots_needed(data_length, k);
+    if needed > total_slots {
+        return Err(SteganoError::NoMessageFound);
+    }
+    let lsbs: Vec<u8> = (0..needed).map(get_slot).collect();
+    let data = matrix::extract_payload_data(&lsbs, k, data_length);
+
+    let report = redundancy::IntegrityReport {
+        bits_clean: needed,
+        bits_corrected: 0,
+        estimated_ber: 0.0,
+    };
+    Ok((data, report))
+}
+
+/// Largest message (in bytes) that fits in this carrier at grouping `k` and
+/// redundancy factor `r`, accounting for the fixed header.
+pub fn max_audio_capacity(num_samples: usize, k: u8, r: u8) -> usize {
+    let remaining_slots = num_samples.saturating_sub(fixed_header_bits(PositionScheme::Random));
+    let logical_remaining = remaining_slots / r as usize;
+    matrix::max_payload_bytes(logical_remaining, k)
+}
+
+/// Decode just enough of a carrier to report its usable sample count.
+pub fn get_audio_capacity(audio_data: &[u8], k: u8, r: u8) -> Result<usize, SteganoError> {
+    let decoded = decode_audio(audio_data)?;
+    Ok(max_audio_capacity(decoded.samples.len(), k, r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `extract_data_audio` must still recover a carrier written by the
+    /// pre-chunk0-4 format (raw `k` header followed immediately by the
+    /// matrix-encoded payload at sample 0, no scheme/salt/redundancy header),
+    /// falling back once the current header-based parse rejects it.
+    #[test]
+    fn extracts_a_pre_chunk0_4_legacy_encoded_carrier() {
+        let spec = AudioSpec { channels: 1, sample_rate: 44100, bits_per_sample: 16 };
+
+        let data = b"Hi";
+        let k = matrix::DEFAULT_K;
+        let needed = matrix::payload_slots_needed(data.len(), k);
+        let mut samples = vec![0i32; needed];
+
+        let mut lsbs = vec![0u8; needed];
+        matrix::embed_payload(&mut lsbs, data, k);
+        for (slot, &bit) in lsbs.iter().enumerate() {
+            samples[slot] = (samples[slot] & !1) | bit as i32;
+        }
+
+        let audio_data = encode_wav(spec, &samples).unwrap();
+        let (recovered, report) = extract_data_audio(&audio_data, "any-passphrase").unwrap();
+        assert_eq!(recovered, data);
+        assert_eq!(report.bits_corrected, 0);
+    }
+}
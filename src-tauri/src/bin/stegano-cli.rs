@@ -0,0 +1,252 @@
+//! Headless CLI for the steganography engine.
+//!
+//! Wraps the same `embed_data_*`/`extract_data_*` functions the Tauri commands
+//! use, but reads and writes carrier files directly from disk instead of
+//! going through the base64 round-trip the desktop app needs for IPC. This
+//! makes the engine usable from shell pipelines and CI without a GUI.
+
+use clap::{Parser, Subcommand};
+use std::io::Read;
+use std::path::PathBuf;
+use stegano_lib::position::PositionScheme;
+use stegano_lib::{SteganoError, audio, format, image_stego, matrix, redundancy};
+
+#[derive(Parser)]
+#[command(name = "stegano-cli", about = "Hide and reveal encrypted messages in images and audio")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Hide an encrypted message in a PNG image
+    EncodeImage {
+        /// Carrier image to read (PNG)
+        #[arg(short, long)]
+        input: PathBuf,
+        /// Where to write the resulting PNG
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Message to hide; read from stdin if omitted
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Passphrase; read from stdin if omitted
+        #[arg(short, long)]
+        passphrase: Option<String>,
+        /// Number of spread-out copies of each bit to embed, majority-voted
+        /// back on decode; higher values trade capacity for resilience to
+        /// carrier corruption
+        #[arg(short = 'r', long, default_value_t = redundancy::DEFAULT_REDUNDANCY)]
+        redundancy: u8,
+        /// Matrix-encoding grouping factor: message bits hidden per group of
+        /// 2^k - 1 cover LSBs, flipping at most one of them; higher values
+        /// trade capacity for fewer modified LSBs
+        #[arg(
+            short = 'k',
+            long = "k-factor",
+            default_value_t = matrix::DEFAULT_K,
+            value_parser = clap::value_parser!(u8).range(1..=matrix::MAX_K as i64)
+        )]
+        k_factor: u8,
+        /// Use sequential (raster-order) embedding positions instead of a
+        /// passphrase-derived pseudorandom permutation
+        #[arg(long)]
+        sequential: bool,
+    },
+    /// Recover a hidden message from a PNG image
+    DecodeImage {
+        /// Carrier image to read (PNG)
+        #[arg(short, long)]
+        input: PathBuf,
+        /// Passphrase; read from stdin if omitted
+        #[arg(short, long)]
+        passphrase: Option<String>,
+    },
+    /// Hide an encrypted message in a WAV or FLAC file
+    EncodeAudio {
+        /// Carrier audio file to read (WAV or FLAC)
+        #[arg(short, long)]
+        input: PathBuf,
+        /// Where to write the resulting audio file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Message to hide; read from stdin if omitted
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Passphrase; read from stdin if omitted
+        #[arg(short, long)]
+        passphrase: Option<String>,
+        /// Number of spread-out copies of each bit to embed, majority-voted
+        /// back on decode; higher values trade capacity for resilience to
+        /// carrier corruption
+        #[arg(short = 'r', long, default_value_t = redundancy::DEFAULT_REDUNDANCY)]
+        redundancy: u8,
+        /// Matrix-encoding grouping factor: message bits hidden per group of
+        /// 2^k - 1 cover LSBs, flipping at most one of them; higher values
+        /// trade capacity for fewer modified LSBs
+        #[arg(
+            short = 'k',
+            long = "k-factor",
+            default_value_t = matrix::DEFAULT_K,
+            value_parser = clap::value_parser!(u8).range(1..=matrix::MAX_K as i64)
+        )]
+        k_factor: u8,
+        /// Use sequential (sample-order) embedding positions instead of a
+        /// passphrase-derived pseudorandom permutation
+        #[arg(long)]
+        sequential: bool,
+    },
+    /// Recover a hidden message from a WAV or FLAC file
+    DecodeAudio {
+        /// Carrier audio file to read (WAV or FLAC)
+        #[arg(short, long)]
+        input: PathBuf,
+        /// Passphrase; read from stdin if omitted
+        #[arg(short, long)]
+        passphrase: Option<String>,
+    },
+    /// Report how many bytes of message a carrier file can hold
+    Capacity {
+        /// Carrier file to inspect (PNG, WAV, or FLAC)
+        #[arg(short, long)]
+        input: PathBuf,
+    },
+}
+
+/// Read one line from stdin, without its line ending, as the value of `field`
+/// when its flag was omitted; exits with an error if stdin is empty.
+fn read_line_from_stdin(field: &str) -> Result<String, SteganoError> {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    if line.is_empty() {
+        eprintln!("error: no {field} given and stdin is empty");
+        std::process::exit(1);
+    }
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Resolve the optional `--message`/`--passphrase` values, reading the
+/// omitted one from stdin. At most one of the two fields on a command may
+/// fall back to stdin, since both would otherwise compete for the same input.
+fn resolve_message_and_passphrase(
+    message: Option<String>,
+    passphrase: Option<String>,
+) -> Result<(String, String), SteganoError> {
+    match (message, passphrase) {
+        (Some(m), Some(p)) => Ok((m, p)),
+        (None, Some(p)) => Ok((read_line_from_stdin("message")?, p)),
+        (Some(m), None) => Ok((m, read_line_from_stdin("passphrase")?)),
+        (None, None) => {
+            eprintln!("error: at most one of --message / --passphrase may be read from stdin");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn resolve_passphrase(passphrase: Option<String>) -> Result<String, SteganoError> {
+    match passphrase {
+        Some(p) => Ok(p),
+        None => read_line_from_stdin("passphrase"),
+    }
+}
+
+/// Print a carrier-integrity summary to stderr so it doesn't interfere with
+/// piping the decoded message itself.
+fn print_integrity_report(report: &redundancy::IntegrityReport) {
+    eprintln!(
+        "integrity: {} bits clean, {} bits corrected, estimated BER {:.4}",
+        report.bits_clean, report.bits_corrected, report.estimated_ber
+    );
+}
+
+fn run(cli: Cli) -> Result<(), SteganoError> {
+    match cli.command {
+        Command::EncodeImage { input, output, message, passphrase, redundancy, k_factor, sequential } => {
+            let (message, passphrase) = resolve_message_and_passphrase(message, passphrase)?;
+            let carrier_data = std::fs::read(&input)?;
+            let img = image::load_from_memory(&carrier_data)?;
+            let encrypted = stegano_lib::encrypt_message(&message, &passphrase)?;
+            let scheme = if sequential { PositionScheme::Sequential } else { PositionScheme::Random };
+            let output_img = image_stego::embed_data_image(
+                &img,
+                &encrypted,
+                &passphrase,
+                k_factor,
+                redundancy,
+                scheme,
+            )?;
+            image::DynamicImage::ImageRgba8(output_img).save(&output)?;
+            println!("Wrote {}", output.display());
+        }
+        Command::DecodeImage { input, passphrase } => {
+            let passphrase = resolve_passphrase(passphrase)?;
+            let carrier_data = std::fs::read(&input)?;
+            let img = image::load_from_memory(&carrier_data)?;
+            let (encrypted, report) = image_stego::extract_data_image(&img, &passphrase)?;
+            println!("{}", stegano_lib::decrypt_message(&encrypted, &passphrase)?);
+            print_integrity_report(&report);
+        }
+        Command::EncodeAudio { input, output, message, passphrase, redundancy, k_factor, sequential } => {
+            let (message, passphrase) = resolve_message_and_passphrase(message, passphrase)?;
+            let carrier_data = std::fs::read(&input)?;
+            let encrypted = stegano_lib::encrypt_message(&message, &passphrase)?;
+            let scheme = if sequential { PositionScheme::Sequential } else { PositionScheme::Random };
+            let output_audio = audio::embed_data_audio(
+                &carrier_data,
+                &encrypted,
+                &passphrase,
+                k_factor,
+                redundancy,
+                scheme,
+            )?;
+            std::fs::write(&output, output_audio)?;
+            println!("Wrote {}", output.display());
+        }
+        Command::DecodeAudio { input, passphrase } => {
+            let passphrase = resolve_passphrase(passphrase)?;
+            let carrier_data = std::fs::read(&input)?;
+            let (encrypted, report) = audio::extract_data_audio(&carrier_data, &passphrase)?;
+            println!("{}", stegano_lib::decrypt_message(&encrypted, &passphrase)?);
+            print_integrity_report(&report);
+        }
+        Command::Capacity { input } => {
+            let carrier_data = std::fs::read(&input)?;
+            let kind = format::sniff(&carrier_data)?;
+            if !kind.supports_embedding() {
+                return Err(SteganoError::UnsupportedForEmbedding(format!("{kind:?}")));
+            }
+            let overhead = stegano_lib::MAGIC_HEADER.len()
+                + stegano_lib::SALT_SIZE
+                + stegano_lib::NONCE_SIZE
+                + 16; // 16 is AES-GCM auth tag
+            let raw_capacity = match kind {
+                format::CarrierKind::Png => {
+                    let img = image::load_from_memory(&carrier_data)?;
+                    image_stego::get_image_capacity(
+                        &img,
+                        matrix::DEFAULT_K,
+                        redundancy::DEFAULT_REDUNDANCY,
+                    )
+                }
+                format::CarrierKind::Wav | format::CarrierKind::Flac => audio::get_audio_capacity(
+                    &carrier_data,
+                    matrix::DEFAULT_K,
+                    redundancy::DEFAULT_REDUNDANCY,
+                )?,
+                format::CarrierKind::Jpeg | format::CarrierKind::Mp3 => {
+                    unreachable!("lossy formats are rejected by supports_embedding above")
+                }
+            };
+            println!("{}", raw_capacity.saturating_sub(overhead));
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,42 @@
+//! Carrier format detection from magic bytes, so callers don't have to know in
+//! advance whether they're handing us an image or an audio file.
+
+use crate::SteganoError;
+
+/// The kind of file a decoded base64 blob appears to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarrierKind {
+    Png,
+    Jpeg,
+    Wav,
+    Flac,
+    Mp3,
+}
+
+impl CarrierKind {
+    /// Whether this crate can embed/extract data in carriers of this kind.
+    /// Lossy formats (JPEG, MP3) would destroy LSB-hidden bits on re-encode.
+    pub fn supports_embedding(self) -> bool {
+        matches!(self, CarrierKind::Png | CarrierKind::Wav | CarrierKind::Flac)
+    }
+}
+
+/// Inspect the leading bytes of `data` and identify the carrier format.
+pub fn sniff(data: &[u8]) -> Result<CarrierKind, SteganoError> {
+    if data.starts_with(b"\x89PNG") {
+        return Ok(CarrierKind::Png);
+    }
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+        return Ok(CarrierKind::Wav);
+    }
+    if data.starts_with(b"fLaC") {
+        return Ok(CarrierKind::Flac);
+    }
+    if data.starts_with(b"\xFF\xD8\xFF") {
+        return Ok(CarrierKind::Jpeg);
+    }
+    if data.starts_with(b"ID3") || data.starts_with(b"\xFF\xFB") {
+        return Ok(CarrierKind::Mp3);
+    }
+    Err(SteganoError::UnrecognizedCarrier)
+}
@@ -4,12 +4,18 @@ use aes_gcm::{
 };
 use argon2::Argon2;
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
-use hound::{WavReader, WavWriter};
-use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use image::{DynamicImage, ImageFormat};
 use rand::RngCore;
 use std::io::Cursor;
 use thiserror::Error;
 
+pub mod audio;
+pub mod format;
+pub mod image_stego;
+pub mod matrix;
+pub mod position;
+pub mod redundancy;
+
 #[derive(Error, Debug)]
 pub enum SteganoError {
     #[error("Image error: {0}")]
@@ -28,6 +34,14 @@ pub enum SteganoError {
     DecryptionFailed,
     #[error("Unsupported audio format")]
     UnsupportedAudioFormat,
+    #[error("Carrier format not recognized")]
+    UnrecognizedCarrier,
+    #[error("Carrier format does not support embedding (lossy codec): {0}")]
+    UnsupportedForEmbedding(String),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Carrier corruption exceeds the correctable threshold")]
+    CorruptionExceedsThreshold,
 }
 
 impl serde::Serialize for SteganoError {
@@ -39,12 +53,34 @@ impl serde::Serialize for SteganoError {
     }
 }
 
-const MAGIC_HEADER: &[u8] = b"STEG";
-const SALT_SIZE: usize = 16;
-const NONCE_SIZE: usize = 12;
+/// A decoded message along with a carrier-integrity report from redundant
+/// embedding (see [`redundancy`]), so the caller can judge how much the
+/// carrier was corrupted.
+#[derive(serde::Serialize)]
+pub struct DecodedMessage {
+    pub message: String,
+    pub bits_clean: usize,
+    pub bits_corrected: usize,
+    pub estimated_ber: f64,
+}
+
+impl DecodedMessage {
+    fn new(message: String, report: redundancy::IntegrityReport) -> Self {
+        Self {
+            message,
+            bits_clean: report.bits_clean,
+            bits_corrected: report.bits_corrected,
+            estimated_ber: report.estimated_ber,
+        }
+    }
+}
+
+pub const MAGIC_HEADER: &[u8] = b"STEG";
+pub const SALT_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12;
 
 /// Derive a 256-bit key from a passphrase using Argon2
-fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SteganoError> {
+pub(crate) fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SteganoError> {
     let mut key = [0u8; 32];
     Argon2::default()
         .hash_password_into(passphrase.as_bytes(), salt, &mut key)
@@ -53,7 +89,7 @@ fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SteganoError> {
 }
 
 /// Encrypt a message using AES-256-GCM
-fn encrypt_message(message: &str, passphrase: &str) -> Result<Vec<u8>, SteganoError> {
+pub fn encrypt_message(message: &str, passphrase: &str) -> Result<Vec<u8>, SteganoError> {
     let mut salt = [0u8; SALT_SIZE];
     let mut nonce_bytes = [0u8; NONCE_SIZE];
     let mut rng = rand::thread_rng();
@@ -80,7 +116,7 @@ fn encrypt_message(message: &str, passphrase: &str) -> Result<Vec<u8>, SteganoEr
 }
 
 /// Decrypt a message using AES-256-GCM
-fn decrypt_message(data: &[u8], passphrase: &str) -> Result<String, SteganoError> {
+pub fn decrypt_message(data: &[u8], passphrase: &str) -> Result<String, SteganoError> {
     // Check magic header
     if data.len() < MAGIC_HEADER.len() + SALT_SIZE + NONCE_SIZE {
         return Err(SteganoError::InvalidFormat);
@@ -107,285 +143,6 @@ fn decrypt_message(data: &[u8], passphrase: &str) -> Result<String, SteganoError
     String::from_utf8(plaintext).map_err(|_| SteganoError::InvalidFormat)
 }
 
-// ============================================================================
-// IMAGE STEGANOGRAPHY
-// ============================================================================
-
-/// Embed data into an image using LSB steganography
-fn embed_data_image(img: &DynamicImage, data: &[u8]) -> Result<RgbaImage, SteganoError> {
-    let (width, height) = img.dimensions();
-    let max_bytes = ((width * height * 3) / 8) as usize - 4; // Reserve 4 bytes for length
-
-    if data.len() > max_bytes {
-        return Err(SteganoError::MessageTooLarge);
-    }
-
-    let mut output = img.to_rgba8();
-    let data_len = data.len() as u32;
-
-    // Create bit stream: 4 bytes for length + actual data
-    let mut bit_stream = Vec::new();
-    for byte in data_len.to_be_bytes() {
-        for i in (0..8).rev() {
-            bit_stream.push((byte >> i) & 1);
-        }
-    }
-    for byte in data {
-        for i in (0..8).rev() {
-            bit_stream.push((byte >> i) & 1);
-        }
-    }
-
-    let mut bit_index = 0;
-    'outer: for y in 0..height {
-        for x in 0..width {
-            if bit_index >= bit_stream.len() {
-                break 'outer;
-            }
-
-            let pixel = output.get_pixel_mut(x, y);
-            let Rgba([r, g, b, a]) = *pixel;
-
-            let new_r = if bit_index < bit_stream.len() {
-                let bit = bit_stream[bit_index];
-                bit_index += 1;
-                (r & 0xFE) | bit
-            } else {
-                r
-            };
-
-            let new_g = if bit_index < bit_stream.len() {
-                let bit = bit_stream[bit_index];
-                bit_index += 1;
-                (g & 0xFE) | bit
-            } else {
-                g
-            };
-
-            let new_b = if bit_index < bit_stream.len() {
-                let bit = bit_stream[bit_index];
-                bit_index += 1;
-                (b & 0xFE) | bit
-            } else {
-                b
-            };
-
-            *pixel = Rgba([new_r, new_g, new_b, a]);
-        }
-    }
-
-    Ok(output)
-}
-
-/// Extract data from an image using LSB steganography
-fn extract_data_image(img: &DynamicImage) -> Result<Vec<u8>, SteganoError> {
-    let (width, height) = img.dimensions();
-    let rgba = img.to_rgba8();
-
-    // First, extract the length (4 bytes = 32 bits)
-    let mut bits = Vec::new();
-    'outer: for y in 0..height {
-        for x in 0..width {
-            let Rgba([r, g, b, _]) = rgba.get_pixel(x, y);
-
-            bits.push(r & 1);
-            bits.push(g & 1);
-            bits.push(b & 1);
-
-            if bits.len() >= 32 {
-                break 'outer;
-            }
-        }
-    }
-
-    if bits.len() < 32 {
-        return Err(SteganoError::NoMessageFound);
-    }
-
-    // Convert first 32 bits to length
-    let mut length_bytes = [0u8; 4];
-    for (i, byte) in length_bytes.iter_mut().enumerate() {
-        for j in 0..8 {
-            *byte |= bits[i * 8 + j] << (7 - j);
-        }
-    }
-    let data_length = u32::from_be_bytes(length_bytes) as usize;
-
-    // Sanity check
-    let max_bytes = ((width * height * 3) / 8) as usize - 4;
-    if data_length > max_bytes || data_length == 0 {
-        return Err(SteganoError::NoMessageFound);
-    }
-
-    // Extract the actual data
-    let total_bits_needed = 32 + data_length * 8;
-    let total_bits_available = (width * height * 3) as usize;
-    if total_bits_needed > total_bits_available {
-        return Err(SteganoError::NoMessageFound);
-    }
-    let mut all_bits = Vec::with_capacity(total_bits_needed);
-
-    'outer2: for y in 0..height {
-        for x in 0..width {
-            let Rgba([r, g, b, _]) = rgba.get_pixel(x, y);
-
-            all_bits.push(r & 1);
-            if all_bits.len() >= total_bits_needed {
-                break 'outer2;
-            }
-
-            all_bits.push(g & 1);
-            if all_bits.len() >= total_bits_needed {
-                break 'outer2;
-            }
-
-            all_bits.push(b & 1);
-            if all_bits.len() >= total_bits_needed {
-                break 'outer2;
-            }
-        }
-    }
-
-    // Convert bits to bytes (skip the first 32 bits which are the length)
-    let mut data = Vec::with_capacity(data_length);
-    for i in 0..data_length {
-        let mut byte = 0u8;
-        for j in 0..8 {
-            byte |= all_bits[32 + i * 8 + j] << (7 - j);
-        }
-        data.push(byte);
-    }
-
-    Ok(data)
-}
-
-// ============================================================================
-// AUDIO STEGANOGRAPHY
-// ============================================================================
-
-/// Embed data into audio samples using LSB steganography
-fn embed_data_audio(audio_data: &[u8], data: &[u8]) -> Result<Vec<u8>, SteganoError> {
-    let cursor = Cursor::new(audio_data);
-    let reader = WavReader::new(cursor).map_err(|e| SteganoError::AudioError(e.to_string()))?;
-
-    let spec = reader.spec();
-    let samples: Vec<i32> = reader
-        .into_samples::<i32>()
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| SteganoError::AudioError(e.to_string()))?;
-
-    // Calculate capacity (1 bit per sample, minus 32 bits for length)
-    let max_bytes = (samples.len() / 8) - 4;
-    if data.len() > max_bytes {
-        return Err(SteganoError::MessageTooLarge);
-    }
-
-    let data_len = data.len() as u32;
-
-    // Create bit stream: 4 bytes for length + actual data
-    let mut bit_stream = Vec::new();
-    for byte in data_len.to_be_bytes() {
-        for i in (0..8).rev() {
-            bit_stream.push((byte >> i) & 1);
-        }
-    }
-    for byte in data {
-        for i in (0..8).rev() {
-            bit_stream.push((byte >> i) & 1);
-        }
-    }
-
-    // Embed bits into samples
-    let mut modified_samples = samples.clone();
-    for (i, bit) in bit_stream.iter().enumerate() {
-        if i < modified_samples.len() {
-            // Clear LSB and set new bit
-            modified_samples[i] = (modified_samples[i] & !1) | (*bit as i32);
-        }
-    }
-
-    // Write output WAV
-    let mut output_buffer = Cursor::new(Vec::new());
-    {
-        let mut writer = WavWriter::new(&mut output_buffer, spec)
-            .map_err(|e| SteganoError::AudioError(e.to_string()))?;
-
-        for sample in modified_samples {
-            match spec.bits_per_sample {
-                8 => writer
-                    .write_sample(sample as i8)
-                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
-                16 => writer
-                    .write_sample(sample as i16)
-                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
-                24 | 32 => writer
-                    .write_sample(sample)
-                    .map_err(|e| SteganoError::AudioError(e.to_string()))?,
-                _ => return Err(SteganoError::UnsupportedAudioFormat),
-            }
-        }
-
-        writer
-            .finalize()
-            .map_err(|e| SteganoError::AudioError(e.to_string()))?;
-    }
-
-    Ok(output_buffer.into_inner())
-}
-
-/// Extract data from audio samples using LSB steganography
-fn extract_data_audio(audio_data: &[u8]) -> Result<Vec<u8>, SteganoError> {
-    let cursor = Cursor::new(audio_data);
-    let reader = WavReader::new(cursor).map_err(|e| SteganoError::AudioError(e.to_string()))?;
-
-    let samples: Vec<i32> = reader
-        .into_samples::<i32>()
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| SteganoError::AudioError(e.to_string()))?;
-
-    if samples.len() < 32 {
-        return Err(SteganoError::NoMessageFound);
-    }
-
-    // Extract length (first 32 bits)
-    let mut length_bits = Vec::new();
-    for sample in samples.iter().take(32) {
-        length_bits.push((sample & 1) as u8);
-    }
-
-    let mut length_bytes = [0u8; 4];
-    for (i, byte) in length_bytes.iter_mut().enumerate() {
-        for j in 0..8 {
-            *byte |= length_bits[i * 8 + j] << (7 - j);
-        }
-    }
-    let data_length = u32::from_be_bytes(length_bytes) as usize;
-
-    // Sanity check
-    let max_bytes = (samples.len() / 8) - 4;
-    if data_length > max_bytes || data_length == 0 {
-        return Err(SteganoError::NoMessageFound);
-    }
-
-    // Extract data bits
-    let total_bits_needed = 32 + data_length * 8;
-    if samples.len() < total_bits_needed {
-        return Err(SteganoError::NoMessageFound);
-    }
-
-    let mut data = Vec::with_capacity(data_length);
-    for i in 0..data_length {
-        let mut byte = 0u8;
-        for j in 0..8 {
-            let sample_index = 32 + i * 8 + j;
-            byte |= ((samples[sample_index] & 1) as u8) << (7 - j);
-        }
-        data.push(byte);
-    }
-
-    Ok(data)
-}
-
 // ============================================================================
 // TAURI COMMANDS - IMAGE
 // ============================================================================
@@ -408,7 +165,14 @@ async fn encode_message(
     let encrypted_data = encrypt_message(&message, &passphrase)?;
 
     // Embed the encrypted data into the image
-    let output_img = embed_data_image(&img, &encrypted_data)?;
+    let output_img = image_stego::embed_data_image(
+        &img,
+        &encrypted_data,
+        &passphrase,
+        matrix::DEFAULT_K,
+        redundancy::DEFAULT_REDUNDANCY,
+        position::PositionScheme::Random,
+    )?;
 
     // Encode the output image as PNG (lossless format is required for steganography)
     let mut output_buffer = Cursor::new(Vec::new());
@@ -419,7 +183,10 @@ async fn encode_message(
 }
 
 #[tauri::command]
-async fn decode_message(image_base64: String, passphrase: String) -> Result<String, SteganoError> {
+async fn decode_message(
+    image_base64: String,
+    passphrase: String,
+) -> Result<DecodedMessage, SteganoError> {
     // Decode the base64 image
     let image_data = BASE64
         .decode(&image_base64)
@@ -429,12 +196,12 @@ async fn decode_message(image_base64: String, passphrase: String) -> Result<Stri
     let img = image::load_from_memory(&image_data)?;
 
     // Extract the hidden data
-    let encrypted_data = extract_data_image(&img)?;
+    let (encrypted_data, report) = image_stego::extract_data_image(&img, &passphrase)?;
 
     // Decrypt the message
     let message = decrypt_message(&encrypted_data, &passphrase)?;
 
-    Ok(message)
+    Ok(DecodedMessage::new(message, report))
 }
 
 #[tauri::command]
@@ -444,11 +211,11 @@ fn get_image_capacity(image_base64: String) -> Result<usize, SteganoError> {
         .map_err(|_| SteganoError::InvalidFormat)?;
 
     let img = image::load_from_memory(&image_data)?;
-    let (width, height) = img.dimensions();
 
     // Calculate max bytes (subtract header overhead: magic + salt + nonce + auth tag)
     let overhead = MAGIC_HEADER.len() + SALT_SIZE + NONCE_SIZE + 16; // 16 is AES-GCM auth tag
-    let raw_capacity = ((width * height * 3) / 8) as usize - 4;
+    let raw_capacity =
+        image_stego::get_image_capacity(&img, matrix::DEFAULT_K, redundancy::DEFAULT_REDUNDANCY);
 
     Ok(raw_capacity.saturating_sub(overhead))
 }
@@ -472,7 +239,14 @@ async fn encode_audio_message(
     let encrypted_data = encrypt_message(&message, &passphrase)?;
 
     // Embed the encrypted data into the audio
-    let output_audio = embed_data_audio(&audio_data, &encrypted_data)?;
+    let output_audio = audio::embed_data_audio(
+        &audio_data,
+        &encrypted_data,
+        &passphrase,
+        matrix::DEFAULT_K,
+        redundancy::DEFAULT_REDUNDANCY,
+        position::PositionScheme::Random,
+    )?;
 
     // Return as base64
     Ok(BASE64.encode(output_audio))
@@ -482,19 +256,19 @@ async fn encode_audio_message(
 async fn decode_audio_message(
     audio_base64: String,
     passphrase: String,
-) -> Result<String, SteganoError> {
+) -> Result<DecodedMessage, SteganoError> {
     // Decode the base64 audio
     let audio_data = BASE64
         .decode(&audio_base64)
         .map_err(|_| SteganoError::InvalidFormat)?;
 
     // Extract the hidden data
-    let encrypted_data = extract_data_audio(&audio_data)?;
+    let (encrypted_data, report) = audio::extract_data_audio(&audio_data, &passphrase)?;
 
     // Decrypt the message
     let message = decrypt_message(&encrypted_data, &passphrase)?;
 
-    Ok(message)
+    Ok(DecodedMessage::new(message, report))
 }
 
 #[tauri::command]
@@ -503,14 +277,119 @@ fn get_audio_capacity(audio_base64: String) -> Result<usize, SteganoError> {
         .decode(&audio_base64)
         .map_err(|_| SteganoError::InvalidFormat)?;
 
-    let cursor = Cursor::new(audio_data);
-    let reader = WavReader::new(cursor).map_err(|e| SteganoError::AudioError(e.to_string()))?;
+    let raw_capacity =
+        audio::get_audio_capacity(&audio_data, matrix::DEFAULT_K, redundancy::DEFAULT_REDUNDANCY)?;
 
-    let num_samples = reader.len() as usize;
+    // Subtract header overhead: magic + salt + nonce + auth tag
+    let overhead = MAGIC_HEADER.len() + SALT_SIZE + NONCE_SIZE + 16; // 16 is AES-GCM auth tag
+
+    Ok(raw_capacity.saturating_sub(overhead))
+}
+
+// ============================================================================
+// TAURI COMMANDS - UNIFIED (FORMAT AUTO-DETECT)
+// ============================================================================
+
+#[tauri::command]
+async fn encode(carrier_base64: String, message: String, passphrase: String) -> Result<String, SteganoError> {
+    let carrier_data = BASE64
+        .decode(&carrier_base64)
+        .map_err(|_| SteganoError::InvalidFormat)?;
+
+    let kind = format::sniff(&carrier_data)?;
+    if !kind.supports_embedding() {
+        return Err(SteganoError::UnsupportedForEmbedding(format!("{kind:?}")));
+    }
+
+    let encrypted_data = encrypt_message(&message, &passphrase)?;
+
+    match kind {
+        format::CarrierKind::Png => {
+            let img = image::load_from_memory(&carrier_data)?;
+            let output_img = image_stego::embed_data_image(
+                &img,
+                &encrypted_data,
+                &passphrase,
+                matrix::DEFAULT_K,
+                redundancy::DEFAULT_REDUNDANCY,
+                position::PositionScheme::Random,
+            )?;
+            let mut output_buffer = Cursor::new(Vec::new());
+            DynamicImage::ImageRgba8(output_img).write_to(&mut output_buffer, ImageFormat::Png)?;
+            Ok(BASE64.encode(output_buffer.into_inner()))
+        }
+        format::CarrierKind::Wav | format::CarrierKind::Flac => {
+            let output_audio = audio::embed_data_audio(
+                &carrier_data,
+                &encrypted_data,
+                &passphrase,
+                matrix::DEFAULT_K,
+                redundancy::DEFAULT_REDUNDANCY,
+                position::PositionScheme::Random,
+            )?;
+            Ok(BASE64.encode(output_audio))
+        }
+        format::CarrierKind::Jpeg | format::CarrierKind::Mp3 => {
+            unreachable!("lossy formats are rejected by supports_embedding above")
+        }
+    }
+}
+
+#[tauri::command]
+async fn decode(carrier_base64: String, passphrase: String) -> Result<DecodedMessage, SteganoError> {
+    let carrier_data = BASE64
+        .decode(&carrier_base64)
+        .map_err(|_| SteganoError::InvalidFormat)?;
+
+    let kind = format::sniff(&carrier_data)?;
+    if !kind.supports_embedding() {
+        return Err(SteganoError::UnsupportedForEmbedding(format!("{kind:?}")));
+    }
+
+    let (encrypted_data, report) = match kind {
+        format::CarrierKind::Png => {
+            let img = image::load_from_memory(&carrier_data)?;
+            image_stego::extract_data_image(&img, &passphrase)?
+        }
+        format::CarrierKind::Wav | format::CarrierKind::Flac => {
+            audio::extract_data_audio(&carrier_data, &passphrase)?
+        }
+        format::CarrierKind::Jpeg | format::CarrierKind::Mp3 => {
+            unreachable!("lossy formats are rejected by supports_embedding above")
+        }
+    };
+
+    let message = decrypt_message(&encrypted_data, &passphrase)?;
+    Ok(DecodedMessage::new(message, report))
+}
+
+#[tauri::command]
+fn capacity(carrier_base64: String) -> Result<usize, SteganoError> {
+    let carrier_data = BASE64
+        .decode(&carrier_base64)
+        .map_err(|_| SteganoError::InvalidFormat)?;
+
+    let kind = format::sniff(&carrier_data)?;
+    if !kind.supports_embedding() {
+        return Err(SteganoError::UnsupportedForEmbedding(format!("{kind:?}")));
+    }
 
-    // Calculate max bytes (subtract header overhead: magic + salt + nonce + auth tag)
     let overhead = MAGIC_HEADER.len() + SALT_SIZE + NONCE_SIZE + 16; // 16 is AES-GCM auth tag
-    let raw_capacity = (num_samples / 8) - 4;
+
+    let raw_capacity = match kind {
+        format::CarrierKind::Png => {
+            let img = image::load_from_memory(&carrier_data)?;
+            image_stego::get_image_capacity(&img, matrix::DEFAULT_K, redundancy::DEFAULT_REDUNDANCY)
+        }
+        format::CarrierKind::Wav | format::CarrierKind::Flac => audio::get_audio_capacity(
+            &carrier_data,
+            matrix::DEFAULT_K,
+            redundancy::DEFAULT_REDUNDANCY,
+        )?,
+        format::CarrierKind::Jpeg | format::CarrierKind::Mp3 => {
+            unreachable!("lossy formats are rejected by supports_embedding above")
+        }
+    };
 
     Ok(raw_capacity.saturating_sub(overhead))
 }
@@ -531,7 +410,10 @@ pub fn run() {
             get_image_capacity,
             encode_audio_message,
             decode_audio_message,
-            get_audio_capacity
+            get_audio_capacity,
+            encode,
+            decode,
+            capacity
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,208 @@
+//! F5-style matrix (syndrome) encoding.
+//!
+//! Plain LSB replacement flips roughly half of all touched bits. Matrix encoding
+//! hides `k` message bits in a group of `n = 2^k - 1` cover LSBs while flipping
+//! at most one of them: for a group with current LSBs `a_1..a_n`, the syndrome
+//! `s = XOR over i in 1..=n of (i * a_i)` (using `i`'s own bits as a mask) already
+//! encodes `s` bits for free. Embedding `m` only requires flipping the LSB at
+//! position `d = s XOR m` (and does nothing if `d == 0`). Extraction just
+//! recomputes the syndrome. `k = 1` degenerates to a single-bit group, which
+//! flips its one LSB exactly when it doesn't already match - i.e. plain LSB
+//! replacement.
+
+/// Default grouping factor: one message bit per cover LSB (today's behavior).
+pub const DEFAULT_K: u8 = 1;
+
+/// Largest grouping factor `embed_bits` can safely use: its per-group message
+/// accumulator is a `u32`, so `k` beyond this would shift-overflow it on a
+/// short final chunk (`group_size` itself shifts a `usize`, so it overflows
+/// once `k` reaches `usize::BITS`). Encoders must reject `k`
+/// above this before it reaches `embed_bits`; decoders read an already-bounded
+/// `k` back off the carrier and only need the weaker `usize::BITS` check in
+/// `valid_k` to avoid panicking on garbage.
+pub const MAX_K: u8 = 32;
+
+/// Number of cover LSBs in a group that hides `k` message bits.
+pub fn group_size(k: u8) -> usize {
+    (1usize << k) - 1
+}
+
+fn syndrome(lsbs: &[u8]) -> u32 {
+    lsbs.iter()
+        .enumerate()
+        .filter(|&(_, &bit)| bit == 1)
+        .fold(0u32, |acc, (idx, _)| acc ^ (idx as u32 + 1))
+}
+
+/// Position (0-based within the group) whose LSB must flip to encode `message`,
+/// or `None` if the group already encodes it.
+fn flip_position(lsbs: &[u8], message: u32) -> Option<usize> {
+    let d = syndrome(lsbs) ^ message;
+    if d == 0 { None } else { Some((d - 1) as usize) }
+}
+
+/// Embed `bit_stream` into `lsbs` in place, `k` bits per group of
+/// `group_size(k)` cover LSBs. `lsbs` must hold at least
+/// `slots_needed(bit_stream.len(), k)` entries. The final group is padded with
+/// zero message bits if `bit_stream.len()` isn't a multiple of `k`.
+pub fn embed_bits(lsbs: &mut [u8], bit_stream: &[u8], k: u8) {
+    let n = group_size(k);
+    for (group_index, message_bits) in bit_stream.chunks(k as usize).enumerate() {
+        let mut message = 0u32;
+        for &bit in message_bits {
+            message = (message << 1) | bit as u32;
+        }
+        message <<= k as usize - message_bits.len(); // pad a short final chunk
+
+        let start = group_index * n;
+        let group = &mut lsbs[start..start + n];
+        if let Some(pos) = flip_position(group, message) {
+            group[pos] ^= 1;
+        }
+    }
+}
+
+/// Recover `num_bits` message bits from `lsbs`, the inverse of `embed_bits`.
+pub fn extract_bits(lsbs: &[u8], k: u8, num_bits: usize) -> Vec<u8> {
+    let n = group_size(k);
+    let mut out = Vec::with_capacity(num_bits);
+    for group in lsbs.chunks(n) {
+        if out.len() >= num_bits {
+            break;
+        }
+        let message = syndrome(group);
+        for i in (0..k).rev() {
+            if out.len() >= num_bits {
+                break;
+            }
+            out.push(((message >> i) & 1) as u8);
+        }
+    }
+    out
+}
+
+/// Number of cover LSBs required to carry `num_bits` message bits at grouping `k`.
+pub fn slots_needed(num_bits: usize, k: u8) -> usize {
+    let groups = num_bits.div_ceil(k as usize);
+    groups * group_size(k)
+}
+
+/// Largest payload (in bytes) that fits in `total_slots` cover LSBs at grouping `k`.
+pub fn max_payload_bytes(total_slots: usize, k: u8) -> usize {
+    let header_slots = K_HEADER_BITS + slots_needed(32, k);
+    if total_slots < header_slots {
+        return 0;
+    }
+    let data_slots = total_slots - header_slots;
+    let data_groups = data_slots / group_size(k);
+    (data_groups * k as usize) / 8
+}
+
+/// `k` is written as 8 raw (ungrouped) LSBs ahead of everything else, so decode
+/// can learn the grouping before it knows how to read anything that follows.
+pub const K_HEADER_BITS: usize = 8;
+
+/// Total cover LSBs needed for a length-prefixed payload of `data_len` bytes,
+/// including the raw `k` header. The length and data fields are each padded
+/// to a whole number of groups independently (see `embed_payload`), so this
+/// is the sum of their two `slots_needed` calls rather than one combined call.
+pub fn payload_slots_needed(data_len: usize, k: u8) -> usize {
+    K_HEADER_BITS + slots_needed(32, k) + slots_needed(data_len * 8, k)
+}
+
+/// Write `k` as raw bits followed by a matrix-encoded 32-bit length and `data`.
+///
+/// The length field is embedded in its own `embed_bits` call so it always
+/// occupies exactly `slots_needed(32, k)` cover LSBs, padded to a whole
+/// number of groups independently of `data` - `read_header` and
+/// `extract_payload_data` rely on the data starting exactly there. Embedding
+/// both as one continuous bit-stream would let the length's final group
+/// bleed into the data's leading bits whenever `k` doesn't evenly divide 32.
+pub fn embed_payload(lsbs: &mut [u8], data: &[u8], k: u8) {
+    for (i, slot) in lsbs.iter_mut().take(K_HEADER_BITS).enumerate() {
+        *slot = (k >> (K_HEADER_BITS - 1 - i)) & 1;
+    }
+
+    let data_len = data.len() as u32;
+    let mut length_bits = Vec::with_capacity(32);
+    for byte in data_len.to_be_bytes() {
+        for i in (0..8).rev() {
+            length_bits.push((byte >> i) & 1);
+        }
+    }
+    let mut data_bits = Vec::with_capacity(data.len() * 8);
+    for byte in data {
+        for i in (0..8).rev() {
+            data_bits.push((byte >> i) & 1);
+        }
+    }
+
+    let length_slots = slots_needed(32, k);
+    let (length_region, data_region) = lsbs[K_HEADER_BITS..].split_at_mut(length_slots);
+    embed_bits(length_region, &length_bits, k);
+    embed_bits(data_region, &data_bits, k);
+}
+
+/// Recover the grouping factor `k` and the declared payload length in bytes
+/// from the start of `lsbs`.
+pub fn read_header(lsbs: &[u8]) -> (u8, usize) {
+    let mut k = 0u8;
+    for &bit in lsbs.iter().take(K_HEADER_BITS) {
+        k = (k << 1) | bit;
+    }
+
+    let length_bits = extract_bits(&lsbs[K_HEADER_BITS..], k, 32);
+    let mut length_bytes = [0u8; 4];
+    for (i, byte) in length_bytes.iter_mut().enumerate() {
+        for j in 0..8 {
+            *byte |= length_bits[i * 8 + j] << (7 - j);
+        }
+    }
+
+    (k, u32::from_be_bytes(length_bytes) as usize)
+}
+
+/// Recover the data bytes following the header, given `k` and the declared
+/// `data_len` in bytes (as returned by `read_header`).
+pub fn extract_payload_data(lsbs: &[u8], k: u8, data_len: usize) -> Vec<u8> {
+    let data_lsbs = &lsbs[K_HEADER_BITS + slots_needed(32, k)..];
+    let data_bits = extract_bits(data_lsbs, k, data_len * 8);
+
+    let mut data = Vec::with_capacity(data_len);
+    for chunk in data_bits.chunks(8) {
+        let mut byte = 0u8;
+        for (j, &bit) in chunk.iter().enumerate() {
+            byte |= bit << (7 - j);
+        }
+        data.push(byte);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embed_payload_round_trips_for_every_grouping_factor() {
+        for k in 1..=8u8 {
+            let data = b"Hi";
+            let mut lsbs = vec![0u8; payload_slots_needed(data.len(), k)];
+            embed_payload(&mut lsbs, data, k);
+
+            let (read_k, data_len) = read_header(&lsbs);
+            assert_eq!(read_k, k);
+            assert_eq!(data_len, data.len());
+            assert_eq!(extract_payload_data(&lsbs, read_k, data_len), data);
+        }
+    }
+
+    #[test]
+    fn embed_bits_flips_at_most_one_lsb_per_group() {
+        let k = 3;
+        let mut lsbs = vec![0u8; group_size(k)];
+        embed_bits(&mut lsbs, &[1, 0, 1], k);
+        assert_eq!(extract_bits(&lsbs, k, 3), vec![1, 0, 1]);
+        assert!(lsbs.iter().filter(|&&bit| bit == 1).count() <= 1);
+    }
+}
@@ -0,0 +1,76 @@
+//! Key-derived pseudorandom embedding order.
+//!
+//! Plain sequential embedding concentrates every changed bit in one corner of
+//! the carrier, which is trivially detectable. Instead we derive a CSPRNG seed
+//! from the passphrase (via a second, independently-salted Argon2 key) and use
+//! it to permute the carrier's candidate slots, so changes are scattered
+//! across the whole carrier. The scheme flag and (if random) the seed salt are
+//! public, non-secret parameters written at a fixed location ahead of
+//! everything else, so decode can reconstruct the same permutation before it
+//! knows anything else about the payload.
+
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha20Rng;
+
+/// Size of the salt used to derive the position-permutation seed key.
+pub const SEED_SALT_SIZE: usize = 16;
+
+/// How carrier slots are ordered for embedding/extraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionScheme {
+    /// Slots are visited 0, 1, 2, ... in order (pre-existing, deterministic behavior).
+    Sequential,
+    /// Slots are visited in a passphrase-derived permutation.
+    Random,
+}
+
+impl PositionScheme {
+    pub fn as_flag(self) -> u8 {
+        match self {
+            PositionScheme::Sequential => 0,
+            PositionScheme::Random => 1,
+        }
+    }
+
+    pub fn from_flag(flag: u8) -> Option<Self> {
+        match flag {
+            0 => Some(PositionScheme::Sequential),
+            1 => Some(PositionScheme::Random),
+            _ => None,
+        }
+    }
+}
+
+/// Build the order in which the `total_slots` candidate carrier slots should
+/// be visited. `Sequential` is the identity order; `Random` is a permutation
+/// seeded from `seed_key`.
+pub fn build_order(scheme: PositionScheme, seed_key: [u8; 32], total_slots: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..total_slots).collect();
+    if scheme == PositionScheme::Random {
+        let mut rng = ChaCha20Rng::from_seed(seed_key);
+        order.shuffle(&mut rng);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_order_is_identity() {
+        let order = build_order(PositionScheme::Sequential, [0u8; 32], 100);
+        assert_eq!(order, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn random_order_is_a_deterministic_permutation_of_the_same_seed() {
+        let mut sorted = build_order(PositionScheme::Random, [7u8; 32], 100);
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        let again = build_order(PositionScheme::Random, [7u8; 32], 100);
+        assert_eq!(build_order(PositionScheme::Random, [7u8; 32], 100), again);
+    }
+}
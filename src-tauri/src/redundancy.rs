@@ -0,0 +1,135 @@
+//! Redundant embedding for surviving minor carrier corruption.
+//!
+//! Each logical payload bit (as seen by [`crate::matrix`]) is written to `r`
+//! physical carrier slots instead of one, spread across the carrier by riding
+//! on the same passphrase-derived permutation [`crate::position`] already
+//! uses. On extraction the `r` copies are majority-voted back into a single
+//! bit, so a minority of flipped copies doesn't corrupt the message. `r = 1`
+//! writes (and reads) a single copy per bit, i.e. today's behavior.
+
+use crate::SteganoError;
+use std::cmp::Ordering;
+
+/// Default redundancy factor: one copy per bit (today's behavior).
+pub const DEFAULT_REDUNDANCY: u8 = 1;
+
+/// A summary of how well a redundantly-embedded payload survived the
+/// carrier, returned alongside the recovered bits so the caller can judge
+/// carrier integrity.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct IntegrityReport {
+    /// Logical bits where every copy agreed.
+    pub bits_clean: usize,
+    /// Logical bits recovered only via majority vote (some copies disagreed).
+    pub bits_corrected: usize,
+    /// Estimated fraction of individual copy-bits that were flipped, based on
+    /// how often a copy disagreed with its group's majority.
+    pub estimated_ber: f64,
+}
+
+/// Number of physical carrier slots needed to redundantly embed
+/// `logical_count` bits at redundancy factor `r`.
+pub fn slots_needed(logical_count: usize, r: u8) -> usize {
+    logical_count * r as usize
+}
+
+/// The majority value among a logical bit's `r` copies, or `None` if there's
+/// no strict majority (e.g. a tie at even `r`) - too corrupted to trust.
+fn majority_vote(copies: &[u8]) -> Option<u8> {
+    let ones = copies.iter().filter(|&&bit| bit == 1).count();
+    match ones.cmp(&(copies.len() - ones)) {
+        Ordering::Greater => Some(1),
+        Ordering::Less => Some(0),
+        Ordering::Equal => None,
+    }
+}
+
+/// Write each of `lsbs` to `r` spread-out positions of `order`, starting at
+/// `order[offset]`. `order` must hold at least
+/// `offset + slots_needed(lsbs.len(), r)` entries.
+pub fn scatter(order: &[usize], offset: usize, lsbs: &[u8], r: u8, mut set_slot: impl FnMut(usize, u8)) {
+    let r = r as usize;
+    for (i, &bit) in lsbs.iter().enumerate() {
+        for copy in 0..r {
+            set_slot(order[offset + i * r + copy], bit);
+        }
+    }
+}
+
+/// Read back `logical_count` redundantly-embedded bits from `order`,
+/// majority-voting each group of `r` copies, and report how much correction
+/// was needed.
+pub fn gather(
+    order: &[usize],
+    offset: usize,
+    logical_count: usize,
+    r: u8,
+    mut get_slot: impl FnMut(usize) -> u8,
+) -> Result<(Vec<u8>, IntegrityReport), SteganoError> {
+    let r = r as usize;
+    let mut bits = Vec::with_capacity(logical_count);
+    let mut bits_clean = 0;
+    let mut bits_corrected = 0;
+    let mut disagreeing_copies = 0usize;
+
+    for i in 0..logical_count {
+        let copies: Vec<u8> = (0..r).map(|copy| get_slot(order[offset + i * r + copy])).collect();
+        let bit = majority_vote(&copies).ok_or(SteganoError::CorruptionExceedsThreshold)?;
+
+        let agreeing = copies.iter().filter(|&&b| b == bit).count();
+        if agreeing == copies.len() {
+            bits_clean += 1;
+        } else {
+            bits_corrected += 1;
+        }
+        disagreeing_copies += copies.len() - agreeing;
+        bits.push(bit);
+    }
+
+    let total_copies = logical_count * r;
+    let estimated_ber = if total_copies == 0 {
+        0.0
+    } else {
+        disagreeing_copies as f64 / total_copies as f64
+    };
+
+    Ok((bits, IntegrityReport { bits_clean, bits_corrected, estimated_ber }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scatter_then_gather_round_trips_with_no_corruption() {
+        let order: Vec<usize> = (0..30).collect();
+        let lsbs = [1u8, 0, 1, 1, 0];
+        let r = 3;
+        let mut slots = vec![0u8; order.len()];
+        scatter(&order, 0, &lsbs, r, |slot, bit| slots[slot] = bit);
+
+        let (recovered, report) = gather(&order, 0, lsbs.len(), r, |slot| slots[slot]).unwrap();
+        assert_eq!(recovered, lsbs);
+        assert_eq!(report.bits_clean, lsbs.len());
+        assert_eq!(report.bits_corrected, 0);
+        assert_eq!(report.estimated_ber, 0.0);
+    }
+
+    #[test]
+    fn gather_majority_votes_past_a_minority_of_flipped_copies() {
+        let order: Vec<usize> = (0..3).collect();
+        let slots = [1u8, 1u8, 0u8]; // one of three copies flipped
+        let (recovered, report) = gather(&order, 0, 1, 3, |slot| slots[slot]).unwrap();
+        assert_eq!(recovered, vec![1]);
+        assert_eq!(report.bits_clean, 0);
+        assert_eq!(report.bits_corrected, 1);
+    }
+
+    #[test]
+    fn gather_reports_corruption_exceeds_threshold_on_an_even_tie() {
+        let order: Vec<usize> = (0..2).collect();
+        let slots = [1u8, 0u8]; // 1-1 tie at r=2, no strict majority
+        let err = gather(&order, 0, 1, 2, |slot| slots[slot]).unwrap_err();
+        assert!(matches!(err, SteganoError::CorruptionExceedsThreshold));
+    }
+}